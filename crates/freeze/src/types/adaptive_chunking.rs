@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::types::metrics::MetricsData;
+
+/// Exponential-moving-average smoothing factor applied to each new bytes-per-block observation.
+const EMA_ALPHA: f64 = 0.2;
+
+/// Tracks an exponential moving average of observed bytes-per-block, per datatype, and derives
+/// the block width that should keep realized output files near a target size.
+///
+/// Backs [`crate::types::chunks::ChunkSizeStrategy::Adaptive`], an adaptive `Subchunk` strategy
+/// alongside the existing fixed-width `BlockChunk` splitting, analogous to content-defined
+/// chunking: instead of a constant block width, the next chunk's width is derived from the
+/// bytes-per-block observed so far.
+///
+/// The EMA lives behind a [`Mutex`] instead of requiring `&mut self`, so the same sizer (cheap to
+/// `clone`, being just an `Arc` underneath) can be shared between whatever is driving requests
+/// and whatever is drawing the next subchunk width — see
+/// [`crate::types::chunks::BlockChunk::adaptive_subchunks`] for why that matters.
+#[derive(Debug, Clone)]
+pub struct AdaptiveChunkSizer {
+    target_file_bytes: u64,
+    min_width: u64,
+    max_width: u64,
+    ema_bytes_per_block: Arc<Mutex<HashMap<&'static str, f64>>>,
+}
+
+impl AdaptiveChunkSizer {
+    /// Creates a sizer targeting `target_file_bytes` per output file, clamping chosen block
+    /// widths to `[min_width, max_width]`. The two bounds are swapped if given in the wrong
+    /// order, so [`Self::next_width`] can clamp unconditionally without risking a `min > max`
+    /// panic.
+    pub fn new(target_file_bytes: u64, min_width: u64, max_width: u64) -> Self {
+        let (min_width, max_width) =
+            if min_width <= max_width { (min_width, max_width) } else { (max_width, min_width) };
+        Self {
+            target_file_bytes,
+            min_width,
+            max_width,
+            ema_bytes_per_block: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Feeds an observed RPC response, as reported by the same [`MetricsData`] fed to
+    /// `metrics_aggregator`, into the per-datatype EMA. `blocks_in_response` is the number of
+    /// blocks the request covered, so the observation can be normalized to bytes-per-block.
+    pub fn observe(&self, datatype: &'static str, data: &MetricsData, blocks_in_response: u64) {
+        if blocks_in_response == 0 {
+            return
+        }
+        let bytes_per_block = data.response_size as f64 / blocks_in_response as f64;
+        let mut ema_bytes_per_block = self.ema_bytes_per_block.lock().unwrap();
+        ema_bytes_per_block
+            .entry(datatype)
+            .and_modify(|ema| *ema = EMA_ALPHA * bytes_per_block + (1.0 - EMA_ALPHA) * *ema)
+            .or_insert(bytes_per_block);
+    }
+
+    /// Returns the block width to use for the next chunk of `datatype`, clamped to
+    /// `[min_width, max_width]`. Falls back to `max_width` until an observation exists, since a
+    /// too-wide first guess is cheaper to correct than a too-narrow one.
+    pub fn next_width(&self, datatype: &str) -> u64 {
+        match self.ema_bytes_per_block.lock().unwrap().get(datatype) {
+            Some(&ema_bytes_per_block) if ema_bytes_per_block > 0.0 => {
+                let width = (self.target_file_bytes as f64 / ema_bytes_per_block).round() as u64;
+                width.clamp(self.min_width, self.max_width)
+            }
+            _ => self.max_width,
+        }
+    }
+}
+
+/// Mean and population standard deviation of a set of realized chunk sizes, reported in
+/// [`crate::types::chunks::ChunkStats`] so users can see the achieved distribution. Units match
+/// whatever was passed in (block widths when reporting realized chunk widths).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RealizedChunkSizeStats {
+    /// Mean of the realized chunk sizes.
+    pub mean: f64,
+    /// Population standard deviation of the realized chunk sizes.
+    pub stddev: f64,
+}
+
+/// Computes mean and standard deviation over a set of realized chunk sizes.
+pub fn realized_chunk_size_stats(sizes: &[u64]) -> RealizedChunkSizeStats {
+    if sizes.is_empty() {
+        return RealizedChunkSizeStats::default()
+    }
+    let mean = sizes.iter().sum::<u64>() as f64 / sizes.len() as f64;
+    let variance = sizes
+        .iter()
+        .map(|&size| {
+            let deviation = size as f64 - mean;
+            deviation * deviation
+        })
+        .sum::<f64>()
+        / sizes.len() as f64;
+    RealizedChunkSizeStats { mean, stddev: variance.sqrt() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(response_size: u64) -> MetricsData {
+        MetricsData { method_name: "get_logs", duration: 0, response_size }
+    }
+
+    #[test]
+    fn next_width_before_any_observation_is_max_width() {
+        let sizer = AdaptiveChunkSizer::new(1_000_000, 10, 1_000);
+        assert_eq!(sizer.next_width("get_logs"), 1_000);
+    }
+
+    #[test]
+    fn next_width_tracks_observed_bytes_per_block() {
+        let sizer = AdaptiveChunkSizer::new(1_000_000, 1, 1_000_000);
+        // 1000 bytes for 10 blocks -> 100 bytes/block, so width ~= 1_000_000 / 100 = 10_000.
+        sizer.observe("get_logs", &data(1_000), 10);
+        assert_eq!(sizer.next_width("get_logs"), 10_000);
+    }
+
+    #[test]
+    fn next_width_is_clamped_to_bounds() {
+        let sizer = AdaptiveChunkSizer::new(1_000_000, 50, 100);
+        sizer.observe("get_logs", &data(1), 1_000_000); // ~0 bytes/block -> would want a huge width
+        assert_eq!(sizer.next_width("get_logs"), 100);
+    }
+
+    #[test]
+    fn new_swaps_reversed_min_and_max_instead_of_panicking() {
+        let sizer = AdaptiveChunkSizer::new(1_000_000, 1_000, 10);
+        assert_eq!(sizer.next_width("get_logs"), 1_000); // max_width after the swap
+        sizer.observe("get_logs", &data(1_000_000_000), 1); // 1e9 bytes/block -> would want a ~0 width
+        assert_eq!(sizer.next_width("get_logs"), 10); // min_width after the swap
+    }
+
+    #[test]
+    fn observe_ignores_zero_blocks_in_response() {
+        let sizer = AdaptiveChunkSizer::new(1_000_000, 1, 1_000_000);
+        sizer.observe("get_logs", &data(1_000), 0);
+        assert_eq!(sizer.next_width("get_logs"), 1_000_000); // unchanged, still max_width
+    }
+
+    #[test]
+    fn realized_chunk_size_stats_of_empty_slice_is_default() {
+        assert_eq!(realized_chunk_size_stats(&[]), RealizedChunkSizeStats::default());
+    }
+
+    #[test]
+    fn realized_chunk_size_stats_of_uniform_sizes_has_zero_stddev() {
+        let stats = realized_chunk_size_stats(&[10, 10, 10]);
+        assert_eq!(stats.mean, 10.0);
+        assert_eq!(stats.stddev, 0.0);
+    }
+
+    #[test]
+    fn realized_chunk_size_stats_computes_mean_and_stddev() {
+        let stats = realized_chunk_size_stats(&[2, 4, 4, 4, 5, 5, 7, 9]);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.stddev, 2.0);
+    }
+}
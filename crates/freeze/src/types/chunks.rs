@@ -0,0 +1,250 @@
+use crate::types::{realized_chunk_size_stats, AdaptiveChunkSizer, RealizedChunkSizeStats};
+
+/// Common behavior shared by all chunk types: how many individual items they cover.
+pub trait ChunkData {
+    /// Number of individual items (blocks, transactions, addresses, ...) in this chunk.
+    fn size(&self) -> u64;
+}
+
+/// A contiguous or explicit set of block numbers to collect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockChunk {
+    /// A contiguous range of block numbers, `[start, end)`.
+    Range(u64, u64),
+    /// An explicit, possibly non-contiguous, list of block numbers.
+    Numbers(Vec<u64>),
+}
+
+impl BlockChunk {
+    /// Materializes the chunk into its block numbers.
+    pub fn block_numbers(&self) -> Vec<u64> {
+        match self {
+            BlockChunk::Range(start, end) => (*start..*end).collect(),
+            BlockChunk::Numbers(numbers) => numbers.clone(),
+        }
+    }
+
+    /// Draws subchunks one at a time, re-deriving each one's width from `strategy` at the moment
+    /// it's drawn rather than fixing a single width for the whole split.
+    ///
+    /// Under `ChunkSizeStrategy::Fixed` this yields the same blocks in the same widths as
+    /// [`Subchunk::subchunk`], always as `BlockChunk::Numbers`, and without stats. Under
+    /// `ChunkSizeStrategy::Adaptive`, it's what makes the sizing genuinely *online*: call
+    /// `sizer.observe(...)` with the response for the subchunk just drawn before pulling the next
+    /// one from the returned iterator, and that subchunk's width reflects the updated EMA.
+    /// [`Subchunk::subchunk`] can't do this — it draws every subchunk before the caller has had a
+    /// chance to observe a single response, so its adaptivity only shows up *across* separate
+    /// top-level calls, not within one.
+    pub fn adaptive_subchunks<'a>(
+        &self,
+        strategy: &'a ChunkSizeStrategy,
+    ) -> impl Iterator<Item = BlockChunk> + 'a {
+        let mut remaining: std::collections::VecDeque<u64> = self.block_numbers().into();
+        std::iter::from_fn(move || {
+            if remaining.is_empty() {
+                return None
+            }
+            let width = match strategy {
+                ChunkSizeStrategy::Fixed { chunk_size } => *chunk_size,
+                ChunkSizeStrategy::Adaptive { datatype, sizer } => sizer.next_width(datatype),
+            }
+            .max(1) as usize;
+
+            let take = width.min(remaining.len());
+            let taken: Vec<u64> = remaining.drain(..take).collect();
+            Some(BlockChunk::Numbers(taken))
+        })
+    }
+}
+
+impl ChunkData for BlockChunk {
+    fn size(&self) -> u64 {
+        match self {
+            BlockChunk::Range(start, end) => end.saturating_sub(*start),
+            BlockChunk::Numbers(numbers) => numbers.len() as u64,
+        }
+    }
+}
+
+macro_rules! list_chunk {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(pub Vec<Vec<u8>>);
+
+        impl ChunkData for $name {
+            fn size(&self) -> u64 {
+                self.0.len() as u64
+            }
+        }
+    };
+}
+list_chunk!(AddressChunk, "An explicit, non-contiguous set of addresses to collect.");
+list_chunk!(CallDataChunk, "An explicit, non-contiguous set of call datas to collect.");
+list_chunk!(SlotChunk, "An explicit, non-contiguous set of storage slots to collect.");
+list_chunk!(TopicChunk, "An explicit, non-contiguous set of log topics to collect.");
+list_chunk!(TransactionChunk, "An explicit, non-contiguous set of transaction hashes to collect.");
+
+/// Umbrella over all chunk dimensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chunk {
+    /// chunk of blocks
+    Block(BlockChunk),
+    /// chunk of transactions
+    Transaction(TransactionChunk),
+    /// chunk of addresses
+    Address(AddressChunk),
+    /// chunk of storage slots
+    Slot(SlotChunk),
+    /// chunk of call datas
+    CallData(CallDataChunk),
+    /// chunk of log topics
+    Topic(TopicChunk),
+}
+
+/// Aggregate stats describing the subchunks produced by a [`Subchunk::subchunk`] split.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChunkStats {
+    /// Number of subchunks produced.
+    pub n_chunks: usize,
+    /// Total number of items (e.g. blocks) covered across all subchunks.
+    pub total_size: u64,
+    /// Mean/stddev of the realized subchunk widths, populated under
+    /// [`ChunkSizeStrategy::Adaptive`] so users can see the achieved distribution; `None` under
+    /// fixed-width chunking, where every subchunk is the same width by construction.
+    pub realized_size: Option<RealizedChunkSizeStats>,
+}
+
+/// How wide each subchunk produced by [`Subchunk::subchunk`] should be.
+#[derive(Debug, Clone)]
+pub enum ChunkSizeStrategy {
+    /// Fixed-width chunks of `chunk_size` items each (the historical behavior).
+    Fixed {
+        /// width of each subchunk, in items (e.g. blocks)
+        chunk_size: u64,
+    },
+    /// Adaptively-sized chunks, targeting a stable output file size by tracking observed
+    /// bytes-per-block via an [`AdaptiveChunkSizer`].
+    Adaptive {
+        /// datatype whose bytes-per-block EMA drives the next chunk width
+        datatype: &'static str,
+        /// shared sizer, fed from [`crate::types::MetricsData`] as responses come in
+        sizer: AdaptiveChunkSizer,
+    },
+}
+
+/// Splits a chunk into a vec of smaller subchunks according to a [`ChunkSizeStrategy`].
+pub trait Subchunk: Sized {
+    /// Splits `self` into subchunks, returning them alongside stats about the split.
+    ///
+    /// Under `ChunkSizeStrategy::Adaptive`, this derives one width from the sizer's EMA *at the
+    /// time `subchunk` is called* and applies it to every subchunk in the split — there's no
+    /// per-subchunk feedback loop here, since the whole `Vec` is produced before the caller has
+    /// issued a single request. Adaptivity therefore only shows up *across* separate top-level
+    /// `subchunk()` calls (e.g. one per datatype per partition, as later calls see whatever the
+    /// sizer has learned since). For a width that responds to its own subchunks' responses as
+    /// they're drawn, use [`BlockChunk::adaptive_subchunks`] instead.
+    fn subchunk(&self, strategy: &ChunkSizeStrategy) -> (Vec<Self>, ChunkStats);
+}
+
+impl Subchunk for BlockChunk {
+    fn subchunk(&self, strategy: &ChunkSizeStrategy) -> (Vec<BlockChunk>, ChunkStats) {
+        let numbers = self.block_numbers();
+        if numbers.is_empty() {
+            return (Vec::new(), ChunkStats::default())
+        }
+
+        let chunk_size = match strategy {
+            ChunkSizeStrategy::Fixed { chunk_size } => *chunk_size,
+            ChunkSizeStrategy::Adaptive { datatype, sizer } => sizer.next_width(datatype),
+        }
+        .max(1);
+
+        let subchunks: Vec<BlockChunk> = numbers
+            .chunks(chunk_size as usize)
+            .map(|slice| match self {
+                BlockChunk::Range(..) => BlockChunk::Range(slice[0], slice[slice.len() - 1] + 1),
+                BlockChunk::Numbers(_) => BlockChunk::Numbers(slice.to_vec()),
+            })
+            .collect();
+
+        let realized_size = match strategy {
+            ChunkSizeStrategy::Adaptive { .. } => {
+                let widths: Vec<u64> = subchunks.iter().map(ChunkData::size).collect();
+                Some(realized_chunk_size_stats(&widths))
+            }
+            ChunkSizeStrategy::Fixed { .. } => None,
+        };
+
+        let stats =
+            ChunkStats { n_chunks: subchunks.len(), total_size: numbers.len() as u64, realized_size };
+
+        (subchunks, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::metrics::MetricsData;
+
+    #[test]
+    fn subchunk_of_empty_range_is_empty() {
+        let (subchunks, stats) =
+            BlockChunk::Range(5, 5).subchunk(&ChunkSizeStrategy::Fixed { chunk_size: 10 });
+        assert!(subchunks.is_empty());
+        assert_eq!(stats, ChunkStats::default());
+    }
+
+    #[test]
+    fn subchunk_fixed_splits_evenly() {
+        let (subchunks, stats) =
+            BlockChunk::Range(0, 10).subchunk(&ChunkSizeStrategy::Fixed { chunk_size: 5 });
+        assert_eq!(subchunks, vec![BlockChunk::Range(0, 5), BlockChunk::Range(5, 10)]);
+        assert_eq!(stats.n_chunks, 2);
+        assert_eq!(stats.total_size, 10);
+        assert_eq!(stats.realized_size, None);
+    }
+
+    #[test]
+    fn subchunk_fixed_handles_a_remainder() {
+        let (subchunks, stats) =
+            BlockChunk::Range(0, 7).subchunk(&ChunkSizeStrategy::Fixed { chunk_size: 5 });
+        assert_eq!(subchunks, vec![BlockChunk::Range(0, 5), BlockChunk::Range(5, 7)]);
+        assert_eq!(stats.n_chunks, 2);
+    }
+
+    #[test]
+    fn subchunk_adaptive_reports_realized_size() {
+        let strategy =
+            ChunkSizeStrategy::Adaptive { datatype: "get_logs", sizer: AdaptiveChunkSizer::new(100, 1, 4) };
+        let (subchunks, stats) = BlockChunk::Range(0, 10).subchunk(&strategy);
+        assert_eq!(subchunks.len(), 3); // width falls back to max_width (4) -> 4, 4, 2
+        assert!(stats.realized_size.is_some());
+    }
+
+    #[test]
+    fn adaptive_subchunks_reflects_observations_made_between_draws() {
+        let sizer = AdaptiveChunkSizer::new(1_000, 1, 1_000);
+        let strategy = ChunkSizeStrategy::Adaptive { datatype: "get_logs", sizer: sizer.clone() };
+        let mut drawn = BlockChunk::Range(0, 1_100).adaptive_subchunks(&strategy);
+
+        // Before any observation, width falls back to max_width (1_000), so the first subchunk
+        // is 1_000 blocks wide.
+        assert_eq!(drawn.next(), Some(BlockChunk::Numbers((0..1_000).collect())));
+
+        // Observing a response mid-stream changes the width of the *next* subchunk drawn from
+        // the same iterator -- that's the online part `Subchunk::subchunk` can't do.
+        // 1_000 bytes for 100 blocks -> 10 bytes/block, so width = 1_000 / 10 = 100.
+        sizer.observe("get_logs", &MetricsData { method_name: "get_logs", duration: 0, response_size: 1_000 }, 100);
+        assert_eq!(sizer.next_width("get_logs"), 100);
+        assert_eq!(drawn.next(), Some(BlockChunk::Numbers((1_000..1_100).collect())));
+    }
+
+    #[test]
+    fn adaptive_subchunks_of_empty_range_yields_nothing() {
+        let strategy =
+            ChunkSizeStrategy::Adaptive { datatype: "get_logs", sizer: AdaptiveChunkSizer::new(100, 1, 4) };
+        assert_eq!(BlockChunk::Range(5, 5).adaptive_subchunks(&strategy).next(), None);
+    }
+}
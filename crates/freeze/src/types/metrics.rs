@@ -1,5 +1,6 @@
 use comfy_table::{presets::UTF8_FULL, Cell, Table};
 use human_bytes::human_bytes;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tokio::sync::mpsc;
 
@@ -10,7 +11,10 @@ type ResponseSize = u64;
 type ResponseTime = u128;
 
 /// Metric datapoint
-#[derive(Debug)]
+///
+/// `Copy` so a single datapoint can be forwarded to more than one consumer, e.g. by
+/// [`crate::types::metrics_server::fanout`].
+#[derive(Debug, Clone, Copy)]
 pub struct MetricsData {
     /// Method name. Example `get_logs`
     pub method_name: &'static str,
@@ -21,7 +25,7 @@ pub struct MetricsData {
 }
 
 /// Metrics report over a specific RPC method.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MetricsReport {
     /// Maximum size of all responses.
     max_size: ResponseSize,
@@ -34,15 +38,26 @@ pub struct MetricsReport {
     /// Average waiting time for a response.
     avg_size: ResponseSize,
     /// Average size of a response.
-    avg_time: ResponseTime,
+    pub(crate) avg_time: ResponseTime,
     /// Total duration of all requests.
     total_duration: ResponseTime,
     /// Total size of all responses.
-    total_size: ResponseSize,
+    pub(crate) total_size: ResponseSize,
+    /// 50th percentile waiting time for a response.
+    p50_time: ResponseTime,
+    /// 90th percentile waiting time for a response.
+    p90_time: ResponseTime,
+    /// 99th percentile waiting time for a response.
+    pub(crate) p99_time: ResponseTime,
+    /// Number of requests observed for this method.
+    pub(crate) request_count: usize,
 }
 impl MetricsReport {
     /// Pretty prints report into a table.
-    pub fn pretty_print(reports: HashMap<&str, MetricsReport>) {
+    ///
+    /// Takes the reports by reference so the caller can also pass them to e.g.
+    /// [`crate::types::metrics_output::write_metrics`] without recomputing the aggregation.
+    pub fn pretty_print(reports: &HashMap<&str, MetricsReport>) {
         let mut table = Table::new();
         table.load_preset(UTF8_FULL).set_header(vec![
             "Method",
@@ -51,6 +66,9 @@ impl MetricsReport {
             "Max Time (s)",
             "Min Time (s)",
             "Avg Time (s)",
+            "P50 Time (s)",
+            "P90 Time (s)",
+            "P99 Time (s)",
             "Avg Size (KB)",
             "Total Duration (s)",
             "Total Size (KB)",
@@ -64,6 +82,9 @@ impl MetricsReport {
                 Cell::new(&format!("{:.6}", report.max_time as f64 / 1_000_000_000.0)),
                 Cell::new(&format!("{:.6}", report.min_time as f64 / 1_000_000_000.0)),
                 Cell::new(&format!("{:.6}", report.avg_time as f64 / 1_000_000_000.0)),
+                Cell::new(&format!("{:.6}", report.p50_time as f64 / 1_000_000_000.0)),
+                Cell::new(&format!("{:.6}", report.p90_time as f64 / 1_000_000_000.0)),
+                Cell::new(&format!("{:.6}", report.p99_time as f64 / 1_000_000_000.0)),
                 Cell::new(&format!("{:.2}", human_bytes(report.avg_size as f64))),
                 Cell::new(&format!("{:.6}", report.total_duration as f64 / 1_000_000_000.0)),
                 Cell::new(&format!("{:.2}", human_bytes(report.total_size as f64))),
@@ -71,6 +92,37 @@ impl MetricsReport {
         }
         println!("{table}")
     }
+
+    /// Renders the report's numeric columns as a single CSV row (without the method name),
+    /// using raw nanoseconds and bytes rather than the human-formatted strings used by
+    /// [`Self::pretty_print`], so the output is analysis-ready.
+    pub(crate) fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.max_size,
+            self.min_size,
+            self.avg_size,
+            self.total_size,
+            self.max_time,
+            self.min_time,
+            self.avg_time,
+            self.p50_time,
+            self.p90_time,
+            self.p99_time,
+            self.total_duration,
+            self.request_count,
+        )
+    }
+}
+
+/// Returns the value at the given percentile (0-100) of an ascending-sorted slice,
+/// using the `ceil(p/100 * (n-1))` rank definition.
+fn percentile(sorted: &[ResponseTime], p: f64) -> ResponseTime {
+    if sorted.is_empty() {
+        return 0
+    }
+    let rank = (p / 100.0 * (sorted.len() - 1) as f64).ceil() as usize;
+    sorted[rank.min(sorted.len() - 1)]
 }
 
 /// Collects and aggregate metrics returning a report for each method at the end.
@@ -97,6 +149,14 @@ pub async fn metrics_aggregator(
             let avg_size =
                 if !sizes.is_empty() { sizes.iter().sum::<u64>() / sizes.len() as u64 } else { 0 };
             let avg_time = if !times.is_empty() { total_duration / times.len() as u128 } else { 0 };
+
+            let request_count = times.len();
+            let mut sorted_times = times;
+            sorted_times.sort_unstable();
+            let p50_time = percentile(&sorted_times, 50.0);
+            let p90_time = percentile(&sorted_times, 90.0);
+            let p99_time = percentile(&sorted_times, 99.0);
+
             (
                 method,
                 MetricsReport {
@@ -108,8 +168,74 @@ pub async fn metrics_aggregator(
                     avg_size,
                     total_duration,
                     total_size,
+                    p50_time,
+                    p90_time,
+                    p99_time,
+                    request_count,
                 },
             )
         })
         .collect()
 }
+
+#[cfg(test)]
+impl MetricsReport {
+    /// Test-only constructor, so tests elsewhere in the crate (e.g. `metrics_baseline`) can
+    /// build a report without going through `metrics_aggregator`.
+    pub(crate) fn test_report(
+        avg_time: ResponseTime,
+        p99_time: ResponseTime,
+        total_size: ResponseSize,
+        request_count: usize,
+    ) -> Self {
+        Self {
+            max_size: total_size,
+            min_size: total_size,
+            max_time: p99_time,
+            min_time: avg_time,
+            avg_size: total_size,
+            avg_time,
+            total_duration: avg_time,
+            total_size,
+            p50_time: avg_time,
+            p90_time: p99_time,
+            p99_time,
+            request_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_empty_slice_is_zero() {
+        assert_eq!(percentile(&[], 50.0), 0);
+        assert_eq!(percentile(&[], 99.0), 0);
+    }
+
+    #[test]
+    fn percentile_of_single_sample_is_that_sample() {
+        let sorted = [42];
+        assert_eq!(percentile(&sorted, 50.0), 42);
+        assert_eq!(percentile(&sorted, 90.0), 42);
+        assert_eq!(percentile(&sorted, 99.0), 42);
+    }
+
+    #[test]
+    fn percentile_of_equal_samples_is_that_value() {
+        let sorted = [5, 5, 5, 5];
+        assert_eq!(percentile(&sorted, 50.0), 5);
+        assert_eq!(percentile(&sorted, 99.0), 5);
+    }
+
+    #[test]
+    fn percentile_uses_ceil_p_over_100_times_n_minus_1_rank() {
+        // n = 10, so rank = ceil(p/100 * 9)
+        let sorted: Vec<ResponseTime> = (1..=10).map(|i| i * 10).collect();
+        assert_eq!(percentile(&sorted, 50.0), 60); // ceil(4.5) = 5 -> sorted[5]
+        assert_eq!(percentile(&sorted, 90.0), 100); // ceil(8.1) = 9 -> sorted[9]
+        assert_eq!(percentile(&sorted, 99.0), 100); // ceil(8.91) = 9 -> sorted[9]
+    }
+}
@@ -0,0 +1,290 @@
+use comfy_table::{presets::UTF8_FULL, Cell, Table};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::types::metrics::MetricsReport;
+
+/// Default fractional increase (e.g. `0.1` = 10%) beyond which [`compare`] flags a method as
+/// regressed.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 0.1;
+
+/// Per-method delta between a baseline run and the current run.
+#[derive(Debug)]
+pub struct MetricsDelta {
+    /// Absolute change in average time, in nanoseconds (current - baseline).
+    pub avg_time_delta_ns: i128,
+    /// Percent change in average time relative to the baseline.
+    pub avg_time_delta_pct: f64,
+    /// Absolute change in p99 time, in nanoseconds (current - baseline).
+    pub p99_time_delta_ns: i128,
+    /// Percent change in p99 time relative to the baseline.
+    pub p99_time_delta_pct: f64,
+    /// Absolute change in total response size, in bytes (current - baseline).
+    pub total_size_delta: i64,
+    /// Percent change in total response size relative to the baseline.
+    pub total_size_delta_pct: f64,
+    /// Absolute change in request count (current - baseline).
+    pub request_count_delta: i64,
+    /// Whether avg time, p99 time, or total size regressed beyond the configured threshold.
+    pub regressed: bool,
+}
+
+/// Outcome of comparing one method between a baseline and a current report set.
+#[derive(Debug)]
+pub enum MethodComparison {
+    /// Method present in both runs, with computed deltas.
+    Compared(MetricsDelta),
+    /// Method only present in the current run.
+    New,
+    /// Method only present in the baseline run.
+    Missing,
+}
+
+/// Saves an aggregated metrics report set to `path` as JSON, for later comparison via [`compare`].
+pub fn save_baseline(path: &Path, reports: &HashMap<&str, MetricsReport>) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(reports)
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+    fs::write(path, json)
+}
+
+/// Loads a previously saved baseline report set from `path`.
+pub fn load_baseline(path: &Path) -> io::Result<HashMap<String, MetricsReport>> {
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+}
+
+fn pct_delta(baseline: f64, current: f64) -> f64 {
+    if baseline == 0.0 {
+        if current == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY
+        }
+    } else {
+        (current - baseline) / baseline * 100.0
+    }
+}
+
+/// Compares `current` against `baseline`, flagging methods whose avg time, p99 time, or total
+/// size regressed by more than `threshold` (a fractional increase, e.g. `0.1` for 10%).
+pub fn compare(
+    baseline: &HashMap<String, MetricsReport>,
+    current: &HashMap<&str, MetricsReport>,
+    threshold: f64,
+) -> HashMap<String, MethodComparison> {
+    let methods: HashSet<&str> = baseline
+        .keys()
+        .map(String::as_str)
+        .chain(current.keys().copied())
+        .collect();
+
+    methods
+        .into_iter()
+        .map(|method| {
+            let comparison = match (baseline.get(method), current.get(method)) {
+                (Some(base), Some(curr)) => {
+                    let avg_time_delta_ns = curr.avg_time as i128 - base.avg_time as i128;
+                    let avg_time_delta_pct = pct_delta(base.avg_time as f64, curr.avg_time as f64);
+                    let p99_time_delta_ns = curr.p99_time as i128 - base.p99_time as i128;
+                    let p99_time_delta_pct = pct_delta(base.p99_time as f64, curr.p99_time as f64);
+                    let total_size_delta = curr.total_size as i64 - base.total_size as i64;
+                    let total_size_delta_pct =
+                        pct_delta(base.total_size as f64, curr.total_size as f64);
+                    let request_count_delta = curr.request_count as i64 - base.request_count as i64;
+
+                    let regressed = avg_time_delta_pct > threshold * 100.0
+                        || p99_time_delta_pct > threshold * 100.0
+                        || total_size_delta_pct > threshold * 100.0;
+
+                    MethodComparison::Compared(MetricsDelta {
+                        avg_time_delta_ns,
+                        avg_time_delta_pct,
+                        p99_time_delta_ns,
+                        p99_time_delta_pct,
+                        total_size_delta,
+                        total_size_delta_pct,
+                        request_count_delta,
+                        regressed,
+                    })
+                }
+                (None, Some(_)) => MethodComparison::New,
+                (Some(_), None) => MethodComparison::Missing,
+                (None, None) => unreachable!("method name came from one of the two maps"),
+            };
+            (method.to_string(), comparison)
+        })
+        .collect()
+}
+
+/// Pretty prints a comparison table: one row per method, with sign-aware `Δ%` columns for avg
+/// time, p99 time and total size, and `new`/`missing` rows clearly marked.
+pub fn pretty_print_comparison(comparisons: HashMap<String, MethodComparison>) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).set_header(vec![
+        "Method",
+        "Avg Time (s) Δ%",
+        "P99 Time (s) Δ%",
+        "Total Size (KB) Δ%",
+        "Requests Δ",
+        "Regressed",
+    ]);
+
+    for (method, comparison) in comparisons {
+        match comparison {
+            MethodComparison::Compared(delta) => {
+                table.add_row(vec![
+                    Cell::new(&method),
+                    Cell::new(&format_pct(delta.avg_time_delta_pct)),
+                    Cell::new(&format_pct(delta.p99_time_delta_pct)),
+                    Cell::new(&format_pct(delta.total_size_delta_pct)),
+                    Cell::new(&format!("{:+}", delta.request_count_delta)),
+                    Cell::new(if delta.regressed { "yes" } else { "no" }),
+                ]);
+            }
+            MethodComparison::New => {
+                table.add_row(vec![
+                    Cell::new(&method),
+                    Cell::new("new"),
+                    Cell::new("new"),
+                    Cell::new("new"),
+                    Cell::new("new"),
+                    Cell::new("-"),
+                ]);
+            }
+            MethodComparison::Missing => {
+                table.add_row(vec![
+                    Cell::new(&method),
+                    Cell::new("missing"),
+                    Cell::new("missing"),
+                    Cell::new("missing"),
+                    Cell::new("missing"),
+                    Cell::new("-"),
+                ]);
+            }
+        }
+    }
+    println!("{table}")
+}
+
+fn format_pct(pct: f64) -> String {
+    if pct.is_infinite() {
+        "+∞%".to_string()
+    } else {
+        format!("{pct:+.2}%")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pct_delta_zero_baseline_and_zero_current_is_zero() {
+        assert_eq!(pct_delta(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn pct_delta_zero_baseline_and_nonzero_current_is_infinite() {
+        assert_eq!(pct_delta(0.0, 5.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn pct_delta_computes_signed_percent_change() {
+        assert_eq!(pct_delta(100.0, 150.0), 50.0);
+        assert_eq!(pct_delta(100.0, 50.0), -50.0);
+    }
+
+    fn reports(
+        avg_time: u128,
+        p99_time: u128,
+        total_size: u64,
+    ) -> HashMap<&'static str, MetricsReport> {
+        HashMap::from([(
+            "get_logs",
+            MetricsReport::test_report(avg_time, p99_time, total_size, 10),
+        )])
+    }
+
+    fn baseline(avg_time: u128, p99_time: u128, total_size: u64) -> HashMap<String, MetricsReport> {
+        HashMap::from([(
+            "get_logs".to_string(),
+            MetricsReport::test_report(avg_time, p99_time, total_size, 10),
+        )])
+    }
+
+    #[test]
+    fn compare_flags_regression_strictly_above_threshold() {
+        let base = baseline(1_000, 2_000, 1_000);
+        let current = reports(1_200, 2_000, 1_000); // avg time up 20%
+
+        let within_threshold = compare(&base, &current, 0.2);
+        match &within_threshold["get_logs"] {
+            MethodComparison::Compared(delta) => assert!(
+                !delta.regressed,
+                "20% increase should not exceed a 20% threshold"
+            ),
+            other => panic!("expected Compared, got {other:?}"),
+        }
+
+        let over_threshold = compare(&base, &current, 0.1);
+        match &over_threshold["get_logs"] {
+            MethodComparison::Compared(delta) => assert!(
+                delta.regressed,
+                "20% increase should exceed a 10% threshold"
+            ),
+            other => panic!("expected Compared, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn compare_does_not_flag_improvement() {
+        let base = baseline(1_000, 2_000, 1_000);
+        let current = reports(500, 1_000, 500); // everything improved
+
+        match &compare(&base, &current, 0.1)["get_logs"] {
+            MethodComparison::Compared(delta) => {
+                assert!(!delta.regressed);
+                assert_eq!(delta.avg_time_delta_pct, -50.0);
+            }
+            other => panic!("expected Compared, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn compare_marks_method_only_in_current_as_new() {
+        let base = baseline(1_000, 2_000, 1_000);
+        let mut current = reports(1_000, 2_000, 1_000);
+        current.insert("trace_block", MetricsReport::test_report(1, 1, 1, 1));
+
+        let comparisons = compare(&base, &current, 0.1);
+        assert!(matches!(comparisons["trace_block"], MethodComparison::New));
+        assert!(matches!(
+            comparisons["get_logs"],
+            MethodComparison::Compared(_)
+        ));
+    }
+
+    #[test]
+    fn compare_marks_method_only_in_baseline_as_missing() {
+        let mut base = baseline(1_000, 2_000, 1_000);
+        base.insert(
+            "trace_block".to_string(),
+            MetricsReport::test_report(1, 1, 1, 1),
+        );
+        let current = reports(1_000, 2_000, 1_000);
+
+        let comparisons = compare(&base, &current, 0.1);
+        assert!(matches!(
+            comparisons["trace_block"],
+            MethodComparison::Missing
+        ));
+        assert!(matches!(
+            comparisons["get_logs"],
+            MethodComparison::Compared(_)
+        ));
+    }
+}
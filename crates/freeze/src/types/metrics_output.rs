@@ -0,0 +1,41 @@
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Write},
+    path::Path,
+};
+
+use crate::types::{files::FileFormat, metrics::MetricsReport, metrics_baseline::save_baseline};
+
+/// Writes `reports` to `path` in `format`, analysis-ready: raw nanoseconds and bytes rather than
+/// the human-formatted strings [`MetricsReport::pretty_print`] prints to the terminal.
+///
+/// This reuses the same [`FileFormat`] selection as the data output, so a run can be pointed at
+/// `metrics.json` or `metrics.csv` with the same `--output-format` flag used for datasets.
+pub fn write_metrics(
+    format: FileFormat,
+    path: &Path,
+    reports: &HashMap<&str, MetricsReport>,
+) -> io::Result<()> {
+    match format {
+        // `save_baseline` already serializes a `HashMap<&str, MetricsReport>` to pretty JSON;
+        // reuse it here instead of re-implementing the same serialize-and-write routine.
+        FileFormat::Json => save_baseline(path, reports),
+        FileFormat::Csv => write_metrics_csv(path, reports),
+        other => Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("metrics output does not support {other:?}, use json or csv"),
+        )),
+    }
+}
+
+fn write_metrics_csv(path: &Path, reports: &HashMap<&str, MetricsReport>) -> io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    writeln!(
+        file,
+        "method,max_size_bytes,min_size_bytes,avg_size_bytes,total_size_bytes,max_time_ns,min_time_ns,avg_time_ns,p50_time_ns,p90_time_ns,p99_time_ns,total_duration_ns,request_count"
+    )?;
+    for (method, report) in reports {
+        writeln!(file, "{},{}", method, report.to_csv_row())?;
+    }
+    Ok(())
+}
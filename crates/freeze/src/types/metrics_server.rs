@@ -0,0 +1,207 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::{io::AsyncWriteExt, net::TcpListener, sync::mpsc, sync::RwLock};
+
+use crate::types::metrics::MetricsData;
+
+/// Upper bounds (in nanoseconds) of the logarithmic duration histogram buckets, mirroring the
+/// Prometheus convention of cumulative `le` (less-than-or-equal) buckets plus a `+Inf` bucket.
+const DURATION_BUCKETS_NS: &[u64] = &[
+    1_000,          // 1µs
+    10_000,         // 10µs
+    100_000,        // 100µs
+    1_000_000,      // 1ms
+    10_000_000,     // 10ms
+    100_000_000,    // 100ms
+    1_000_000_000,  // 1s
+    10_000_000_000, // 10s
+    100_000_000_000, // 100s
+];
+
+/// Atomically-updated counters for a single RPC method, scraped without blocking the collector.
+#[derive(Debug, Default)]
+struct MethodCounters {
+    requests_total: AtomicU64,
+    response_bytes_total: AtomicU64,
+    /// Cumulative per-bucket counts, one slot per entry in [`DURATION_BUCKETS_NS`] plus `+Inf`.
+    duration_buckets: Vec<AtomicU64>,
+    duration_sum_ns: AtomicU64,
+}
+
+impl MethodCounters {
+    fn new() -> Self {
+        Self {
+            duration_buckets: (0..=DURATION_BUCKETS_NS.len()).map(|_| AtomicU64::new(0)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn record(&self, response_size: u64, duration_ns: u128) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        self.response_bytes_total.fetch_add(response_size, Ordering::Relaxed);
+        self.duration_sum_ns.fetch_add(duration_ns.min(u64::MAX as u128) as u64, Ordering::Relaxed);
+        for (bucket, upper_bound) in self.duration_buckets.iter().zip(DURATION_BUCKETS_NS) {
+            if duration_ns <= *upper_bound as u128 {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        // `+Inf` bucket always gets the observation.
+        self.duration_buckets[DURATION_BUCKETS_NS.len()].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Splits a single `mpsc::Receiver<MetricsData>` into two receivers fed from the same stream,
+/// by spawning a task that forwards each received item to both downstream channels.
+/// `mpsc::Receiver` is single-consumer, so this is what lets live scraping via
+/// [`MetricsRegistry::collect`] run alongside [`crate::types::metrics::metrics_aggregator`]
+/// without either one needing the other's receiver.
+///
+/// The two returned receivers are not equivalent: the first is guaranteed every datapoint
+/// (backpressure against it slows the whole fanout, same as a plain unsplit channel), and is
+/// meant for [`crate::types::metrics::metrics_aggregator`], which a final report's correctness
+/// depends on. The second is best-effort and meant for [`MetricsRegistry::collect`] -- Prometheus
+/// scraping is inherently sampled, so if its channel is full, the fanout task drops the incoming
+/// datapoint for that side rather than blocking. A slow or stalled scrape consumer can
+/// therefore never back up and stall the aggregator.
+pub fn fanout(
+    mut receiver: mpsc::Receiver<MetricsData>,
+) -> (mpsc::Receiver<MetricsData>, mpsc::Receiver<MetricsData>) {
+    let (tx_reliable, rx_reliable) = mpsc::channel(1024);
+    let (tx_lossy, rx_lossy) = mpsc::channel(1024);
+    tokio::spawn(async move {
+        while let Some(data) = receiver.recv().await {
+            // Guaranteed delivery: a dropped receiver (e.g. aggregation already finished)
+            // shouldn't stop the lossy side from still being fed.
+            let _ = tx_reliable.send(data).await;
+
+            // Best-effort: never block on the scrape side. If it's full, this datapoint is
+            // dropped for it, rather than waiting for the scraper to drain.
+            let _ = tx_lossy.try_send(data);
+        }
+    });
+    (rx_reliable, rx_lossy)
+}
+
+/// Shared store of per-method atomic counters. The method map sits behind an `RwLock`: updates
+/// and scrapes take it for reading, and it is taken for writing only when a method is first seen.
+#[derive(Debug, Default, Clone)]
+pub struct MetricsRegistry {
+    methods: Arc<RwLock<HashMap<&'static str, Arc<MethodCounters>>>>,
+}
+
+impl MetricsRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the registry from an `mpsc::Receiver<MetricsData>`. Intended to run as an
+    /// independent task so a live scrape never blocks, and never is blocked by, end-of-run
+    /// aggregation.
+    ///
+    /// `mpsc::Receiver` is single-consumer, so this cannot be handed the same receiver that
+    /// [`crate::types::metrics::metrics_aggregator`] drains — use [`fanout`] to split one
+    /// producer's output into a receiver for each, passing the lossy (second) one here.
+    pub async fn collect(&self, mut receiver: mpsc::Receiver<MetricsData>) {
+        while let Some(MetricsData { method_name, response_size, duration }) = receiver.recv().await
+        {
+            let counters = {
+                let methods = self.methods.read().await;
+                methods.get(method_name).cloned()
+            };
+            let counters = match counters {
+                Some(counters) => counters,
+                None => {
+                    let mut methods = self.methods.write().await;
+                    methods.entry(method_name).or_insert_with(|| Arc::new(MethodCounters::new())).clone()
+                }
+            };
+            counters.record(response_size, duration);
+        }
+    }
+
+    /// Renders all counters in Prometheus text exposition format.
+    pub async fn render(&self) -> String {
+        let methods = self.methods.read().await;
+        let mut out = String::new();
+
+        out.push_str("# HELP cryo_rpc_requests_total Total number of RPC requests per method.\n");
+        out.push_str("# TYPE cryo_rpc_requests_total counter\n");
+        for (method, counters) in methods.iter() {
+            out.push_str(&format!(
+                "cryo_rpc_requests_total{{method=\"{method}\"}} {}\n",
+                counters.requests_total.load(Ordering::Relaxed)
+            ));
+        }
+
+        out.push_str("# HELP cryo_rpc_response_bytes_total Total bytes received per method.\n");
+        out.push_str("# TYPE cryo_rpc_response_bytes_total counter\n");
+        for (method, counters) in methods.iter() {
+            out.push_str(&format!(
+                "cryo_rpc_response_bytes_total{{method=\"{method}\"}} {}\n",
+                counters.response_bytes_total.load(Ordering::Relaxed)
+            ));
+        }
+
+        out.push_str("# HELP cryo_rpc_duration_seconds Request duration in seconds per method.\n");
+        out.push_str("# TYPE cryo_rpc_duration_seconds histogram\n");
+        for (method, counters) in methods.iter() {
+            for (bucket, upper_bound) in counters.duration_buckets.iter().zip(DURATION_BUCKETS_NS) {
+                let le = *upper_bound as f64 / 1_000_000_000.0;
+                out.push_str(&format!(
+                    "cryo_rpc_duration_seconds_bucket{{method=\"{method}\",le=\"{le}\"}} {}\n",
+                    bucket.load(Ordering::Relaxed)
+                ));
+            }
+            out.push_str(&format!(
+                "cryo_rpc_duration_seconds_bucket{{method=\"{method}\",le=\"+Inf\"}} {}\n",
+                counters.duration_buckets[DURATION_BUCKETS_NS.len()].load(Ordering::Relaxed)
+            ));
+            out.push_str(&format!(
+                "cryo_rpc_duration_seconds_sum{{method=\"{method}\"}} {}\n",
+                counters.duration_sum_ns.load(Ordering::Relaxed) as f64 / 1_000_000_000.0
+            ));
+            out.push_str(&format!(
+                "cryo_rpc_duration_seconds_count{{method=\"{method}\"}} {}\n",
+                counters.requests_total.load(Ordering::Relaxed)
+            ));
+        }
+
+        out
+    }
+}
+
+/// Serves `/metrics` in Prometheus text exposition format on `addr`, until the process exits.
+///
+/// Feed `registry` from the lossy half of a [`fanout`] of the producer's `MetricsData` channel
+/// (via [`MetricsRegistry::collect`]), so the scrape handler sees datapoints without stealing any
+/// from, or ever blocking, end-of-run aggregation.
+///
+/// Nothing here is wired to a CLI flag: whether to call this at all, and what `addr` to bind, is
+/// left entirely to the caller. Neither this function nor the registry it serves does any work
+/// unless a caller spawns this and feeds it a receiver.
+pub async fn serve_prometheus_metrics(
+    addr: std::net::SocketAddr,
+    registry: MetricsRegistry,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            let body = registry.render().await;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
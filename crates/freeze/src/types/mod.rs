@@ -30,6 +30,30 @@ pub mod execution;
 /// report generation
 pub mod reports;
 
+/// rpc metrics collection and reporting
+pub mod metrics;
+pub use metrics::{metrics_aggregator, MetricsData, MetricsReport};
+
+/// live Prometheus exporter for rpc metrics, fed from the same channel as [`metrics_aggregator`]
+pub mod metrics_server;
+pub use metrics_server::{fanout, serve_prometheus_metrics, MetricsRegistry};
+
+/// baseline comparison of metrics reports across runs
+pub mod metrics_baseline;
+pub use metrics_baseline::{
+    compare, load_baseline, pretty_print_comparison, save_baseline, MethodComparison,
+    MetricsDelta, DEFAULT_REGRESSION_THRESHOLD,
+};
+
+/// adaptive block-chunk sizing driven by observed bytes-per-block, backing
+/// [`chunks::ChunkSizeStrategy::Adaptive`]
+pub mod adaptive_chunking;
+pub use adaptive_chunking::{realized_chunk_size_stats, AdaptiveChunkSizer, RealizedChunkSizeStats};
+
+/// machine-readable (JSON/CSV) metrics output, via the same [`FileFormat`] used for data
+pub mod metrics_output;
+pub use metrics_output::write_metrics;
+
 /// type specifications for dataframes
 #[macro_use]
 pub mod dataframes;
@@ -46,8 +70,8 @@ pub mod schemas;
 pub mod summaries;
 
 pub use chunks::{
-    AddressChunk, BlockChunk, CallDataChunk, Chunk, ChunkData, ChunkStats, SlotChunk, Subchunk,
-    TopicChunk, TransactionChunk,
+    AddressChunk, BlockChunk, CallDataChunk, Chunk, ChunkData, ChunkSizeStrategy, ChunkStats,
+    SlotChunk, Subchunk, TopicChunk, TransactionChunk,
 };
 pub use conversions::{ToVecHex, ToVecU8};
 pub use dataframes::*;